@@ -33,12 +33,18 @@
 //! # fn main() { }
 //! ```
 
+#[doc(hidden)]
+pub use core::cell::Cell as __Cell;
+
 /// Check if an expression matches a refutable pattern.
 ///
 /// Syntax: `matches!(` *expression* `,` *pattern* `)`
 ///
 /// Return a boolean, true if the expression matches the pattern, false otherwise.
 ///
+/// With Rust 1.46 or later, this also works in a `const fn` body or in the initializer
+/// of a `const` or `static` item, as long as any guard only calls `const fn`s.
+///
 /// # Examples
 ///
 /// ```
@@ -72,12 +78,167 @@ macro_rules! matches {
     }
 }
 
+/// Check if an expression matches any of several refutable patterns, each with its own guard.
+///
+/// Syntax: `matches_any!(` *expression* `,` *pattern* [`if` *guard*] `,` ... `)`
+///
+/// Return a boolean, true if the expression matches one of the patterns and its guard holds,
+/// false otherwise.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate matches;
+///
+/// fn main() {
+///     let is_small_or_even = |x: Option<u32>| matches_any!(x, Some(n) if n < 10, Some(n) if n % 2 == 0);
+///     assert!(is_small_or_even(Some(3)));
+///     assert!(is_small_or_even(Some(12)));
+///     assert!(!is_small_or_even(Some(13)));
+///     assert!(!is_small_or_even(None));
+/// }
+/// ```
+#[macro_export]
+macro_rules! matches_any {
+    ($expression:expr, $($($pattern:pat)|+ $(if $guard:expr)?),+ $(,)?) => {
+        match $expression {
+            $($($pattern)|+ $(if $guard)? => true,)+
+            _ => false
+        }
+    }
+}
+
+/// Check if several expressions each match their corresponding refutable pattern.
+///
+/// Syntax: `matches_tuple!((` *expression* `,` ... `), (` *pattern* [`if` *guard*] `,` ... `))`
+///
+/// Return a boolean, true if every expression matches its pattern and every guard holds,
+/// false otherwise. Guards can use bindings from any of the patterns.
+///
+/// Unlike with `matches!`, the expressions are moved into a tuple before matching.
+/// Pass references to match values that are not `Copy` without consuming them.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate matches;
+///
+/// fn main() {
+///     let a = Some(4);
+///     let b: Result<u32, ()> = Ok(5);
+///     assert!(matches_tuple!((a, b), (Some(x) if x > 3, Ok(y) if y > x)));
+///     assert!(!matches_tuple!((a, b), (None, Ok(_))));
+///
+///     let name = Some(String::from("Ferris"));
+///     assert!(matches_tuple!((&name, &b), (Some(s) if s.starts_with('F'), Ok(_))));
+///     assert_eq!(name.unwrap(), "Ferris");
+/// }
+/// ```
+#[macro_export]
+macro_rules! matches_tuple {
+    (($($expression:expr),+), ($($pattern:pat $(if $guard:expr)?),+)) => {
+        match ($($expression,)+) {
+            ($($pattern,)+) if true $($(&& $guard)?)+ => true,
+            _ => false
+        }
+    }
+}
+
+/// Split an iterable into runs of consecutive elements
+/// that either all match or all do not match a refutable pattern.
+///
+/// Syntax: `group_consecutive_matches!(` *iterable* `,` *pattern* `)`
+///
+/// Return an iterator of `Vec`s, starting a new run at each transition between matching
+/// and non-matching elements. The pattern is matched against a reference to each element,
+/// so any bindings (for example, in a guard) are references.
+///
+/// The expansion uses `std`, and requires Rust 1.34 or later.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate matches;
+///
+/// fn main() {
+///     let runs: Vec<Vec<i32>> = group_consecutive_matches!(vec![1, 3, 4, 6, 7], n if n % 2 == 1)
+///         .collect();
+///     assert_eq!(runs, [vec![1, 3], vec![4, 6], vec![7]]);
+/// }
+/// ```
+#[macro_export]
+macro_rules! group_consecutive_matches {
+    ($iterable:expr, $($pattern:tt)+) => {{
+        let mut iter = ::std::iter::IntoIterator::into_iter($iterable).peekable();
+        ::std::iter::from_fn(move || {
+            let first = match iter.next() {
+                Some(item) => item,
+                None => return None,
+            };
+            let run_matches = match &first {
+                $($pattern)+ => true,
+                _ => false
+            };
+            let mut run = ::std::vec::Vec::new();
+            run.push(first);
+            loop {
+                let next_matches = match iter.peek() {
+                    Some(item) => match item {
+                        $($pattern)+ => true,
+                        _ => false
+                    },
+                    None => break,
+                };
+                if next_matches != run_matches {
+                    break
+                }
+                run.extend(iter.next());
+            }
+            Some(run)
+        })
+    }}
+}
+
+/// Take the leading elements of an iterable that match a refutable pattern.
+///
+/// Syntax: `take_while_matches!(` *iterable* `,` *pattern* `)`
+///
+/// Return an iterator that stops at the first element that does not match.
+/// The pattern is matched against a reference to each element,
+/// so any bindings (for example, in a guard) are references.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate matches;
+///
+/// fn main() {
+///     let small: Vec<u8> = take_while_matches!(vec![1, 2, 7, 3], n if *n < 5).collect();
+///     assert_eq!(small, [1, 2]);
+/// }
+/// ```
+#[macro_export]
+macro_rules! take_while_matches {
+    ($iterable:expr, $($pattern:tt)+) => {
+        $iterable.into_iter().take_while(|item| match item {
+            $($pattern)+ => true,
+            _ => false
+        })
+    }
+}
+
 /// Assert that an expression matches a refutable pattern.
 ///
 /// Syntax: `assert_matches!(` *expression* `,` *pattern* `)`
 ///
 /// Panic with a message that shows the expression if it does not match the
-/// pattern.
+/// pattern. When the pattern has a guard, the message also tells whether
+/// the pattern itself did not match or its guard was false.
+/// This includes patterns with several alternatives separated by `|`.
 ///
 /// # Examples
 ///
@@ -88,10 +249,25 @@ macro_rules! matches {
 /// fn main() {
 ///     let data = [1, 2, 3];
 ///     assert_matches!(data.get(1), Some(_));
+///     assert_matches!(data.get(1), Some(x) if *x > 1);
+///     assert_matches!(data.get(1), Some(&1) | Some(&2) if data.len() == 3);
 /// }
 /// ```
 #[macro_export]
 macro_rules! assert_matches {
+    ($expression:expr, $($pattern:pat)|+ if $guard:expr) => {{
+        // Record the guard result through a `Cell`:
+        // older compilers reject assignments in match guards.
+        let guard_failed = $crate::__Cell::new(false);
+        match $expression {
+            $($pattern)|+ if { let ok = $guard; guard_failed.set(!ok); ok } => (),
+            ref e if guard_failed.get() => panic!(
+                "assertion failed: `{:?}` does not match `{}` (pattern matched but guard failed)",
+                e, stringify!($($pattern)|+ if $guard)),
+            ref e => panic!("assertion failed: `{:?}` does not match `{}` (pattern did not match)",
+                            e, stringify!($($pattern)|+ if $guard)),
+        }
+    }};
     ($expression:expr, $($pattern:tt)+) => {
         match $expression {
             $($pattern)+ => (),
@@ -100,14 +276,133 @@ macro_rules! assert_matches {
     }
 }
 
+/// Extract a value from an expression that matches a refutable pattern.
+///
+/// Syntax: `unwrap_match!(` *expression* `,` *pattern* [`if` *guard*] `=>` *result* [`,` *message* ...] `)`
+///
+/// Evaluate to *result*, which can use the pattern’s bindings.
+/// Panic if the expression does not match the pattern,
+/// with a message that shows the expression or with the given `panic!` arguments.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate matches;
+///
+/// #[derive(Debug)]
+/// pub enum Message {
+///     Text(String),
+///     Ping,
+/// }
+///
+/// fn main() {
+///     let message = Message::Text("hello".to_owned());
+///     let text = unwrap_match!(message, Message::Text(s) if !s.is_empty() => s);
+///     assert_eq!(text, "hello");
+///
+///     let ping = Message::Ping;
+///     unwrap_match!(ping, Message::Ping => (), "expected a ping");
+/// }
+/// ```
+#[macro_export]
+macro_rules! unwrap_match {
+    ($expression:expr, $pattern:pat $(if $guard:expr)? => $result:expr) => {
+        match $expression {
+            $pattern $(if $guard)? => $result,
+            ref e => panic!("assertion failed: `{:?}` does not match `{}`",
+                            e, stringify!($pattern $(if $guard)?)),
+        }
+    };
+    ($expression:expr, $pattern:pat $(if $guard:expr)? => $result:expr, $($message:tt)+) => {
+        match $expression {
+            $pattern $(if $guard)? => $result,
+            _ => panic!($($message)+),
+        }
+    };
+}
+
+/// Assert that every element of an iterable matches a refutable pattern.
+///
+/// Syntax: `assert_all_match!(` *iterable* `,` *pattern* `)`
+///
+/// Panic with a message that shows the index and the value of the first element
+/// that does not match the pattern.
+/// The pattern is matched against a reference to each element,
+/// so any bindings (for example, in a guard) are references.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate matches;
+///
+/// fn main() {
+///     let data = [Some(1), Some(2), Some(3)];
+///     assert_all_match!(data.iter(), Some(n) if *n > 0);
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_all_match {
+    ($iterable:expr, $($pattern:tt)+) => {
+        for (index, item) in $iterable.into_iter().enumerate() {
+            match &item {
+                $($pattern)+ => (),
+                _ => panic!("assertion failed: element {} `{:?}` does not match `{}`",
+                            index, item, stringify!($($pattern)+)),
+            }
+        }
+    }
+}
+
+/// Assert that an expression matches a refutable pattern,
+/// and bind the pattern’s variables in the enclosing scope.
+///
+/// Syntax: `let_assert_matches!(let` *pattern* `=` *expression* `)`
+///
+/// Panic with the same message as `assert_matches!` if the expression does not match.
+/// This expands to `let ... else`, which requires Rust 1.65 or later.
+///
+/// Unlike with a plain `let ... else`, the expression is moved into the macro
+/// so that it can be shown in the panic message, even if the pattern only binds by `ref`.
+/// Match a reference to keep using a value that is not `Copy`.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate matches;
+///
+/// fn main() {
+///     let data = [1, 2, 3];
+///     let_assert_matches!(let Some(&second) = data.get(1));
+///     assert_eq!(second, 2);
+///
+///     let name = Some(String::from("Ferris"));
+///     let_assert_matches!(let Some(s) = &name);
+///     assert_eq!(s, "Ferris");
+///     assert_eq!(name.unwrap(), "Ferris");
+/// }
+/// ```
+#[macro_export]
+macro_rules! let_assert_matches {
+    (let $pattern:pat = $expression:expr) => {
+        let value = $expression;
+        let $pattern = value else {
+            panic!("assertion failed: `{:?}` does not match `{}`", value, stringify!($pattern))
+        };
+    }
+}
+
 /// Assert that an expression matches a refutable pattern using debug assertions.
 ///
 /// Syntax: `debug_assert_matches!(` *expression* `,` *pattern* `)`
 ///
-/// If debug assertions are enabled, panic with a message that shows the
-/// expression if it does not match the pattern.
+/// If debug assertions are enabled, panic with the same message as `assert_matches!`
+/// if the expression does not match the pattern.
 ///
-/// When debug assertions are not enabled, this macro does nothing.
+/// When debug assertions are not enabled, this macro does nothing
+/// and the expression is not evaluated.
 ///
 /// # Examples
 ///
@@ -124,10 +419,7 @@ macro_rules! assert_matches {
 macro_rules! debug_assert_matches {
     ($expression:expr, $($pattern:tt)+) => {
         if cfg!(debug_assertions) {
-            match $expression {
-                $($pattern)+ => (),
-                ref e => panic!("assertion failed: `{:?}` does not match `{}`", e, stringify!($($pattern)+)),
-            }
+            $crate::assert_matches!($expression, $($pattern)+)
         }
     }
 }
@@ -141,6 +433,63 @@ fn matches_works() {
     ));
 }
 
+#[test]
+fn matches_works_in_const() {
+    #[derive(Clone, Copy)]
+    enum Shape { Point, Circle(u32), Square(u32) }
+
+    const fn is_round(shape: Shape) -> bool {
+        matches!(shape, Shape::Point | Shape::Circle(_))
+    }
+
+    const fn is_large(shape: Shape) -> bool {
+        matches!(shape, Shape::Circle(size) | Shape::Square(size) if size > 10)
+    }
+
+    const OK: [bool; 5] = [
+        is_round(Shape::Point),
+        is_round(Shape::Square(1)),
+        is_large(Shape::Circle(11)),
+        is_large(Shape::Square(10)),
+        matches!(Shape::Square(3), Shape::Square(1..=5)),
+    ];
+    assert_eq!(OK, [true, false, true, false, true]);
+}
+
+#[test]
+fn matches_tuple_works() {
+    #[derive(Clone, Copy)]
+    enum Sign { Plus, Minus }
+
+    let pair = (Sign::Minus, Some(12));
+    assert!(matches_tuple!((pair.0, pair.1), (Sign::Minus, Some(_))));
+    assert!(matches_tuple!((pair.0, pair.1), (Sign::Minus, Some(n) if n > 10)));
+    assert!(!matches_tuple!((pair.0, pair.1), (Sign::Minus, Some(n) if n > 20)));
+    assert!(!matches_tuple!((pair.0, pair.1), (Sign::Plus, _)));
+    assert!(matches_tuple!((Sign::Plus, pair.0), (Sign::Plus, Sign::Minus)));
+
+    let a: Option<&str> = Some("a");
+    let b: Option<[u8; 2]> = None;
+    let owned = (a.map(|s| s.repeat(2)), b.map(|bytes| bytes.to_vec()));
+    assert!(matches_tuple!((&owned.0, &owned.1), (Some(s) if s == "aa", None)));
+    assert!(!matches_tuple!((&owned.0, &owned.1), (None, _)));
+    assert_eq!(owned.0.unwrap(), "aa");
+}
+
+#[test]
+fn matches_any_works() {
+    fn classify(byte: u8) -> bool {
+        matches_any!(byte, b'+' | b'-', b'0'..=b'9' if byte != b'0', b'a'..=b'z' if byte > b'x')
+    }
+    assert!(classify(b'+'));
+    assert!(classify(b'-'));
+    assert!(classify(b'5'));
+    assert!(!classify(b'0'));
+    assert!(classify(b'y'));
+    assert!(!classify(b'a'));
+    assert!(!classify(b'*'));
+}
+
 #[test]
 fn assert_matches_works() {
     let foo = Some("-12");
@@ -150,6 +499,30 @@ fn assert_matches_works() {
     );
 }
 
+#[test]
+fn let_assert_matches_works() {
+    let foo = Some("-12");
+    let_assert_matches!(let Some(bar) = foo);
+    let_assert_matches!(let (b'-', digits) = (bar.as_bytes()[0], &bar[1..]));
+    assert_eq!(digits, "12");
+
+    #[derive(Debug)]
+    struct Token(u32);
+
+    let token = Some(Token(3));
+    let_assert_matches!(let Some(Token(n)) = &token);
+    assert_eq!(*n, 3);
+    let_assert_matches!(let Some(Token(3)) = token);
+}
+
+#[test]
+#[should_panic(expected = "assertion failed: `None` does not match `Some(bar)`")]
+fn let_assert_matches_panics() {
+    let foo: Option<&str> = None;
+    let_assert_matches!(let Some(bar) = foo);
+    assert_eq!(bar, "unreachable");
+}
+
 #[test]
 #[should_panic(expected = "assertion failed: `Some(\"-AB\")` does not match ")]
 fn assert_matches_panics() {
@@ -159,3 +532,92 @@ fn assert_matches_panics() {
         matches!(bar.as_bytes()[1], b'0'...b'9')
     );
 }
+
+#[test]
+fn assert_all_match_works() {
+    let data = [Ok(1), Ok(2), Ok(3)];
+    assert_all_match!(data.iter().cloned(), Ok::<u8, ()>(_));
+    assert_all_match!(data.iter(), Ok(n) if *n > 0);
+    assert_all_match!(data[..0].iter(), Err(_));
+}
+
+#[test]
+#[should_panic(expected = "assertion failed: element 2 `Err(\"x\")` does not match ")]
+fn assert_all_match_panics() {
+    let data = [Ok(1), Ok(2), Err("x"), Ok(4), Err("y")];
+    assert_all_match!(data.iter(), Ok(_));
+}
+
+#[test]
+fn unwrap_match_works() {
+    let foo = Some("-12");
+    let sign = unwrap_match!(foo, Some(bar) => bar.as_bytes()[0]);
+    assert_eq!(sign, b'-');
+    let digits = unwrap_match!(foo, Some(bar) if bar.len() > 1 => &bar[1..], "no digits in {:?}", foo);
+    assert_eq!(digits, "12");
+}
+
+#[test]
+#[should_panic(expected = "assertion failed: `Some(\"-\")` does not match `Some(bar) if bar.len() > 1`")]
+fn unwrap_match_panics_on_guard() {
+    let foo = Some("-");
+    unwrap_match!(foo, Some(bar) if bar.len() > 1 => bar);
+}
+
+#[test]
+#[should_panic(expected = "no digits in Some(\"-\")")]
+fn unwrap_match_panics_with_message() {
+    let foo = Some("-");
+    unwrap_match!(foo, Some(bar) if bar.len() > 1 => bar, "no digits in {:?}", foo);
+}
+
+#[test]
+#[should_panic(expected = "assertion failed: `Some(\"-AB\")` does not match `Some(bar) if bar.len() > 3` \
+                           (pattern matched but guard failed)")]
+fn assert_matches_panics_on_guard() {
+    let foo = Some("-AB");
+    assert_matches!(foo, Some(bar) if bar.len() > 3);
+}
+
+#[test]
+#[should_panic(expected = "assertion failed: `None` does not match `Some(bar) if bar.len() > 3` \
+                           (pattern did not match)")]
+fn assert_matches_panics_on_pattern_with_guard() {
+    let foo: Option<&str> = None;
+    assert_matches!(foo, Some(bar) if bar.len() > 3);
+}
+
+#[test]
+#[should_panic(expected = "assertion failed: `Some(2)` does not match `Some(1) | Some(2) if false` \
+                           (pattern matched but guard failed)")]
+fn assert_matches_panics_on_guard_with_alternatives() {
+    let foo = Some(2);
+    assert_matches!(foo, Some(1) | Some(2) if false);
+}
+
+#[test]
+#[should_panic(expected = "assertion failed: `Some(3)` does not match `Some(1) | Some(2) if true` \
+                           (pattern did not match)")]
+fn assert_matches_panics_on_alternatives_with_guard() {
+    let foo = Some(3);
+    assert_matches!(foo, Some(1) | Some(2) if true);
+}
+
+#[test]
+fn debug_assert_matches_evaluates_only_with_debug_assertions() {
+    let evaluated = core::cell::Cell::new(0);
+    let next = || {
+        evaluated.set(evaluated.get() + 1);
+        Some(evaluated.get())
+    };
+    debug_assert_matches!(next(), Some(_));
+    debug_assert_matches!(next(), Some(n) if n > 0);
+    assert_eq!(evaluated.get(), if cfg!(debug_assertions) { 2 } else { 0 });
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "(pattern matched but guard failed)"))]
+fn debug_assert_matches_guard_message() {
+    let foo = Some("-AB");
+    debug_assert_matches!(foo, Some(bar) if bar.len() > 3);
+}
@@ -0,0 +1,23 @@
+#[macro_use] extern crate matches;
+
+#[test]
+fn group_consecutive_matches_works() {
+    let results: [Result<u8, &str>; 6] = [Ok(1), Ok(2), Err("a"), Ok(3), Err("b"), Err("c")];
+    let runs: Vec<Vec<Result<u8, &str>>> = group_consecutive_matches!(results.iter().cloned(), Ok(_))
+        .collect();
+    assert_eq!(runs.len(), 4);
+    assert_eq!(runs[0], [Ok(1), Ok(2)]);
+    assert_eq!(runs[1], [Err("a")]);
+    assert_eq!(runs[2], [Ok(3)]);
+    assert_eq!(runs[3], [Err("b"), Err("c")]);
+
+    let mut runs = group_consecutive_matches!(results.iter().cloned(), Ok(n) if *n > 1);
+    assert_eq!(runs.next().unwrap(), [Ok(1)]);
+    assert_eq!(runs.next().unwrap(), [Ok(2)]);
+    assert_eq!(runs.next().unwrap(), [Err("a")]);
+    assert_eq!(runs.next().unwrap(), [Ok(3)]);
+    assert_eq!(runs.next().unwrap(), [Err("b"), Err("c")]);
+    assert!(runs.next().is_none());
+
+    assert!(group_consecutive_matches!(results[..0].iter(), _).next().is_none());
+}
@@ -0,0 +1,13 @@
+#[macro_use] extern crate matches;
+
+#[test]
+fn take_while_matches_works() {
+    let results: Vec<Result<i32, String>> = vec![Ok(1), Ok(2), Err("a".to_owned()), Ok(3)];
+    let leading: Vec<Result<i32, String>> = take_while_matches!(results.clone(), Ok(_)).collect();
+    assert_eq!(leading, [Ok(1), Ok(2)]);
+
+    let leading: Vec<Result<i32, String>> = take_while_matches!(results.clone(), Ok(n) if *n < 2).collect();
+    assert_eq!(leading, [Ok(1)]);
+
+    assert_eq!(take_while_matches!(results, Err(_)).count(), 0);
+}
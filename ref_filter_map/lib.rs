@@ -24,6 +24,9 @@
 //! that are a lot like `Ref::map` and `RefMut::map`,
 //! but return `Option` and take closures that return `Option`.
 //!
+//! `rwlock_read_filter_map` and `rwlock_write_filter_map` do the same
+//! for the guards of `std::sync::RwLock`.
+//!
 //! Internally they use a raw pointer and some `unsafe` code,
 //! but the API they provide is believed to be safe.
 //!
@@ -34,6 +37,9 @@
 //! https://github.com/rust-lang/rust/issues/27746
 
 use std::cell::{Ref, RefMut};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 /// Make a new `Ref` for a optional component of the borrowed data, e.g. an enum variant.
 ///
@@ -96,3 +102,336 @@ pub fn ref_mut_filter_map<
         .map(|new| new as *mut U)
         .map(|raw| RefMut::map(orig, |_| unsafe { &mut *raw }))
 }
+
+/// Make a new `Ref` for a component of the borrowed data that may fail to be found,
+/// forwarding the closure’s error.
+///
+/// Like `ref_filter_map`, but the closure returns `Result`
+/// so that the caller can tell why the component is not there.
+///
+/// # Example
+///
+/// ```
+/// use std::cell::{RefCell, Ref};
+/// use ref_filter_map::ref_try_map;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Error { Empty, TooLong(usize) }
+///
+/// fn first_word(s: &String) -> Result<&str, Error> {
+///     match s.split_whitespace().next() {
+///         None => Err(Error::Empty),
+///         Some(word) if word.len() > 5 => Err(Error::TooLong(word.len())),
+///         Some(word) => Ok(word),
+///     }
+/// }
+///
+/// let c = RefCell::new("hello world".to_owned());
+/// let b: Ref<str> = ref_try_map(c.borrow(), first_word).unwrap();
+/// assert_eq!(&*b, "hello");
+/// drop(b);
+///
+/// *c.borrow_mut() = "  ".to_owned();
+/// assert_eq!(ref_try_map(c.borrow(), first_word).err(), Some(Error::Empty));
+///
+/// *c.borrow_mut() = "goodbye world".to_owned();
+/// assert_eq!(ref_try_map(c.borrow(), first_word).err(), Some(Error::TooLong(7)));
+/// ```
+pub fn ref_try_map<
+    T: ?Sized,
+    U: ?Sized,
+    E,
+    F: FnOnce(&T) -> Result<&U, E>
+>(orig: Ref<T>, f: F) -> Result<Ref<U>, E> {
+    f(&orig)
+        .map(|new| new as *const U)
+        .map(|raw| Ref::map(orig, |_| unsafe { &*raw }))
+}
+
+/// Make a new `RefMut` for a component of the borrowed data that may fail to be found,
+/// forwarding the closure’s error.
+///
+/// Like `ref_mut_filter_map`, but the closure returns `Result`
+/// so that the caller can tell why the component is not there.
+///
+/// # Example
+///
+/// ```
+/// use std::cell::{RefCell, RefMut};
+/// use ref_filter_map::ref_mut_try_map;
+///
+/// let c = RefCell::new(vec![1, 2, 3]);
+/// {
+///     let mut b: RefMut<u32> = ref_mut_try_map(c.borrow_mut(), |v| {
+///         let len = v.len();
+///         v.get_mut(1).ok_or(len)
+///     }).unwrap();
+///     *b = 42;
+/// }
+/// assert_eq!(*c.borrow(), [1, 42, 3]);
+///
+/// let r = ref_mut_try_map(c.borrow_mut(), |v| {
+///     let len = v.len();
+///     v.get_mut(10).ok_or(len)
+/// });
+/// assert_eq!(r.err(), Some(3));
+/// ```
+pub fn ref_mut_try_map<
+    T: ?Sized,
+    U: ?Sized,
+    E,
+    F: FnOnce(&mut T) -> Result<&mut U, E>
+>(mut orig: RefMut<T>, f: F) -> Result<RefMut<U>, E> {
+    f(&mut orig)
+        .map(|new| new as *mut U)
+        .map(|raw| RefMut::map(orig, |_| unsafe { &mut *raw }))
+}
+
+/// Make a new `Ref` for a optional component of the borrowed data,
+/// or give back the original `Ref` if the component is not there.
+///
+/// Like `ref_filter_map`, but the `RefCell` stays borrowed on failure
+/// instead of being released and borrowed again.
+/// This matches the signature of `Ref::filter_map` in recent versions of `std`.
+///
+/// # Example
+///
+/// ```
+/// use std::cell::{RefCell, Ref};
+/// use ref_filter_map::ref_filter_map_or_orig;
+///
+/// let c = RefCell::new(Err("not yet"));
+/// let b1: Ref<Result<u32, &str>> = c.borrow();
+/// let b1: Ref<Result<u32, &str>> = ref_filter_map_or_orig(b1, |o| o.as_ref().ok()).unwrap_err();
+/// assert_eq!(*b1, Err("not yet"));
+/// let b2: Ref<&str> = ref_filter_map_or_orig(b1, |o| o.as_ref().err()).ok().unwrap();
+/// assert_eq!(*b2, "not yet");
+/// ```
+pub fn ref_filter_map_or_orig<
+    T: ?Sized,
+    U: ?Sized,
+    F: FnOnce(&T) -> Option<&U>
+>(orig: Ref<T>, f: F) -> Result<Ref<U>, Ref<T>> {
+    match f(&orig).map(|new| new as *const U) {
+        Some(raw) => Ok(Ref::map(orig, |_| unsafe { &*raw })),
+        None => Err(orig),
+    }
+}
+
+/// Make a new `RefMut` for a optional component of the borrowed data,
+/// or give back the original `RefMut` if the component is not there.
+///
+/// Like `ref_mut_filter_map`, but the `RefCell` stays borrowed on failure
+/// instead of being released and borrowed again.
+/// This matches the signature of `RefMut::filter_map` in recent versions of `std`.
+///
+/// # Example
+///
+/// ```
+/// use std::cell::{RefCell, RefMut};
+/// use ref_filter_map::ref_mut_filter_map_or_orig;
+///
+/// let c = RefCell::new(Err(0));
+/// {
+///     let b1: RefMut<Result<u32, u32>> = c.borrow_mut();
+///     let mut b1 = ref_mut_filter_map_or_orig(b1, |o| o.as_mut().ok()).unwrap_err();
+///     *b1 = Ok(5);
+///     let mut b2: RefMut<u32> = ref_mut_filter_map_or_orig(b1, |o| o.as_mut().ok()).ok().unwrap();
+///     *b2 += 1;
+/// }
+/// assert_eq!(*c.borrow(), Ok(6));
+/// ```
+pub fn ref_mut_filter_map_or_orig<
+    T: ?Sized,
+    U: ?Sized,
+    F: FnOnce(&mut T) -> Option<&mut U>
+>(mut orig: RefMut<T>, f: F) -> Result<RefMut<U>, RefMut<T>> {
+    match f(&mut orig).map(|new| new as *mut U) {
+        Some(raw) => Ok(RefMut::map(orig, |_| unsafe { &mut *raw })),
+        None => Err(orig),
+    }
+}
+
+/// Make a new guard for a optional component of the data protected by a `RwLock`,
+/// e.g. an enum variant.
+///
+/// The lock stays read-locked for as long as the returned guard is alive.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::sync::RwLock;
+/// use ref_filter_map::rwlock_read_filter_map;
+///
+/// let mut map = HashMap::new();
+/// map.insert("foo", "bar".to_owned());
+/// let lock = RwLock::new(map);
+///
+/// let value = rwlock_read_filter_map(lock.read().unwrap(), |m| m.get("foo")).unwrap();
+/// assert_eq!(*value, "bar");
+/// assert!(lock.try_write().is_err());
+/// drop(value);
+///
+/// assert!(rwlock_read_filter_map(lock.read().unwrap(), |m| m.get("baz")).is_none());
+/// assert!(lock.try_write().is_ok());
+/// ```
+pub fn rwlock_read_filter_map<
+    'a,
+    T: ?Sized,
+    U: ?Sized,
+    F: FnOnce(&T) -> Option<&U>
+>(orig: RwLockReadGuard<'a, T>, f: F) -> Option<MappedRwLockReadGuard<'a, T, U>> {
+    f(&orig)
+        .map(|new| new as *const U)
+        .map(|ptr| MappedRwLockReadGuard { _guard: orig, raw: ptr })
+}
+
+/// Make a new guard for a optional component of the data protected by a `RwLock`,
+/// e.g. an enum variant.
+///
+/// The lock stays write-locked for as long as the returned guard is alive.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::sync::RwLock;
+/// use ref_filter_map::rwlock_write_filter_map;
+///
+/// let mut map = HashMap::new();
+/// map.insert("foo", "bar".to_owned());
+/// let lock = RwLock::new(map);
+///
+/// {
+///     let mut value = rwlock_write_filter_map(lock.write().unwrap(), |m| m.get_mut("foo"))
+///         .unwrap();
+///     value.push_str("baz");
+///     assert!(lock.try_read().is_err());
+/// }
+/// assert_eq!(lock.read().unwrap()["foo"], "barbaz");
+///
+/// assert!(rwlock_write_filter_map(lock.write().unwrap(), |m| m.get_mut("baz")).is_none());
+/// assert!(lock.try_read().is_ok());
+/// ```
+pub fn rwlock_write_filter_map<
+    'a,
+    T: ?Sized,
+    U: ?Sized,
+    F: FnOnce(&mut T) -> Option<&mut U>
+>(mut orig: RwLockWriteGuard<'a, T>, f: F) -> Option<MappedRwLockWriteGuard<'a, T, U>> {
+    f(&mut orig)
+        .map(|new| new as *mut U)
+        .map(|ptr| MappedRwLockWriteGuard { _guard: orig, raw: ptr })
+}
+
+/// A read guard for a component of the data protected by a `RwLock`,
+/// returned by `rwlock_read_filter_map`.
+///
+/// It keeps the original guard alive, so the lock stays read-locked
+/// and the component cannot be mutated or moved
+/// for as long as this guard dereferences the raw pointer to it.
+pub struct MappedRwLockReadGuard<'a, T: ?Sized + 'a, U: ?Sized + 'a> {
+    _guard: RwLockReadGuard<'a, T>,
+    raw: *const U,
+}
+
+impl<'a, T: ?Sized, U: ?Sized> Deref for MappedRwLockReadGuard<'a, T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        unsafe { &*self.raw }
+    }
+}
+
+impl<'a, T: ?Sized, U: ?Sized + fmt::Debug> fmt::Debug for MappedRwLockReadGuard<'a, T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// A write guard for a component of the data protected by a `RwLock`,
+/// returned by `rwlock_write_filter_map`.
+///
+/// It keeps the original guard alive, so the lock stays write-locked
+/// and nothing else can access the component
+/// for as long as this guard dereferences the raw pointer to it.
+pub struct MappedRwLockWriteGuard<'a, T: ?Sized + 'a, U: ?Sized + 'a> {
+    _guard: RwLockWriteGuard<'a, T>,
+    raw: *mut U,
+}
+
+impl<'a, T: ?Sized, U: ?Sized> Deref for MappedRwLockWriteGuard<'a, T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        unsafe { &*self.raw }
+    }
+}
+
+impl<'a, T: ?Sized, U: ?Sized> DerefMut for MappedRwLockWriteGuard<'a, T, U> {
+    fn deref_mut(&mut self) -> &mut U {
+        unsafe { &mut *self.raw }
+    }
+}
+
+impl<'a, T: ?Sized, U: ?Sized + fmt::Debug> fmt::Debug for MappedRwLockWriteGuard<'a, T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Chain several projections of a `Ref` (or of a `RefMut` with a leading `mut`),
+/// like nested calls to `ref_filter_map` (or `ref_mut_filter_map`).
+///
+/// Evaluates to `None` as soon as one of the closures returns `None`,
+/// without calling the remaining ones.
+///
+/// # Example
+///
+/// ```
+/// #[macro_use] extern crate ref_filter_map;
+/// use std::cell::{RefCell, Ref};
+/// use std::collections::HashMap;
+///
+/// # fn main() {
+/// let mut map = HashMap::new();
+/// map.insert("k", vec![1, 2, 3]);
+/// let c: RefCell<Result<HashMap<&str, Vec<u32>>, ()>> = RefCell::new(Ok(map));
+///
+/// let b: Ref<u32> = ref_project!(c.borrow(), |e| e.as_ref().ok(), |m| m.get("k"), |v| v.last())
+///     .unwrap();
+/// assert_eq!(*b, 3);
+/// drop(b);
+///
+/// assert!(ref_project!(c.borrow(), |e| e.as_ref().ok(), |m| m.get("z"), |v| v.last()).is_none());
+///
+/// if let Some(mut v) = ref_project!(mut c.borrow_mut(), |e| e.as_mut().ok(), |m| m.get_mut("k")) {
+///     v.push(4);
+/// }
+/// assert_eq!(c.borrow().as_ref().unwrap()["k"], [1, 2, 3, 4]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ref_project {
+    (mut $orig:expr $(, $f:expr)* $(,)*) => {{
+        let projected = Some($orig);
+        $(
+            let projected = match projected {
+                Some(r) => $crate::ref_mut_filter_map(r, $f),
+                None => None,
+            };
+        )*
+        projected
+    }};
+    ($orig:expr $(, $f:expr)* $(,)*) => {{
+        let projected = Some($orig);
+        $(
+            let projected = match projected {
+                Some(r) => $crate::ref_filter_map(r, $f),
+                None => None,
+            };
+        )*
+        projected
+    }};
+}
@@ -12,6 +12,67 @@ macro_rules! try {
 }
 
 
+/// Collect an iterator of `Triable` values into a `Vec`,
+/// or return early from the enclosing function on the first failure.
+#[macro_export]
+macro_rules! collect_triable {
+    ($iterator: expr) => {{
+        let mut vec = ::std::vec::Vec::new();
+        for item in $iterator {
+            vec.push(match $crate::Triable::try(item) {
+                $crate::TriableResult::Expression(value) => value,
+                $crate::TriableResult::EarlyReturn(value) => return value,
+            });
+        }
+        vec
+    }};
+}
+
+
+
+/// Like `try!`, but return early with `Default::default()` on failure,
+/// whatever the return type of the enclosing function.
+#[macro_export]
+macro_rules! triable_or_default {
+    ($expression: expr) => {
+        match $crate::TriableOrDefault::into_option($expression) {
+            ::std::option::Option::Some(value) => value,
+            ::std::option::Option::None => return ::std::default::Default::default(),
+        }
+    };
+}
+
+
+/// Evaluate all of a tuple of `Result`s.
+/// If any of them is `Err`, return early from the enclosing function with `Err`
+/// of a `Vec` of all errors (converted via `From`).
+/// Otherwise, evaluate to a tuple of the `Ok` values.
+///
+/// This macro calls itself by name, so it needs to be imported with `#[macro_use]`.
+#[macro_export]
+macro_rules! triable_all {
+    (($($result: expr),+ $(,)*)) => {{
+        let mut errors = ::std::vec::Vec::new();
+        triable_all!(@next errors [] $($result,)+)
+    }};
+    (@next $errors: ident [$($value: ident)*] $result: expr, $($rest: tt)*) => {{
+        let value = match $result {
+            ::std::result::Result::Ok(value) => ::std::option::Option::Some(value),
+            ::std::result::Result::Err(error) => {
+                $errors.push(error);
+                ::std::option::Option::None
+            }
+        };
+        triable_all!(@next $errors [$($value)* value] $($rest)*)
+    }};
+    (@next $errors: ident [$($value: ident)*]) => {{
+        if !$errors.is_empty() {
+            return ::std::result::Result::Err(::std::convert::From::from($errors))
+        }
+        ($($value.unwrap(),)*)
+    }};
+}
+
 pub enum TriableResult<Expr, Return> {
     Expression(Expr),
     EarlyReturn(Return),
@@ -114,6 +175,34 @@ impl<T> Triable<(), Option<T>> for bool {
 }
 
 
+/// Implementation detail of `triable_or_default!`.
+#[doc(hidden)]
+pub trait TriableOrDefault<T> {
+    fn into_option(self) -> Option<T>;
+}
+
+
+impl<T, E> TriableOrDefault<T> for Result<T, E> {
+    fn into_option(self) -> Option<T> {
+        self.ok()
+    }
+}
+
+
+impl<T> TriableOrDefault<T> for Option<T> {
+    fn into_option(self) -> Option<T> {
+        self
+    }
+}
+
+
+impl TriableOrDefault<()> for bool {
+    fn into_option(self) -> Option<()> {
+        if self { Some(()) } else { None }
+    }
+}
+
+
 
 
 #[test]
@@ -238,3 +327,115 @@ fn bool_to_option() {
     }
     assert_eq!(none(), None);
 }
+
+#[test]
+fn collect() {
+    fn all_ok() -> Result<Vec<i32>, ()> {
+        Ok(collect_triable!([Ok(1), Ok(2), Ok(3)].iter().cloned()))
+    }
+    assert_eq!(all_ok(), Ok(vec![1, 2, 3]));
+
+    #[derive(Debug, PartialEq)]
+    struct Wrapped(&'static str);
+
+    impl From<&'static str> for Wrapped {
+        fn from(error: &'static str) -> Wrapped {
+            Wrapped(error)
+        }
+    }
+
+    fn first_err() -> Result<Vec<i32>, Wrapped> {
+        Ok(collect_triable!([Ok(1), Err("first"), Ok(3), Err("second")].iter().cloned()))
+    }
+    assert_eq!(first_err(), Err(Wrapped("first")));
+}
+
+#[test]
+#[allow(bare_trait_objects)]  // `dyn Trait` requires Rust 1.27
+fn result_to_boxed_error() {
+    use std::error::Error;
+    use std::io;
+
+    fn ok() -> Result<i32, Box<Error>> {
+        let result: Result<i32, io::Error> = Ok(4);
+        Ok(try!(result))
+    }
+    assert_eq!(ok().unwrap(), 4);
+
+    fn err() -> Result<i32, Box<Error>> {
+        let result: Result<i32, io::Error> = Err(io::Error::new(io::ErrorKind::NotFound, "oops"));
+        Ok(try!(result))
+    }
+    assert_eq!(err().unwrap_err().to_string(), "oops");
+}
+
+#[test]
+fn unit_result_statement() {
+    fn check(value: i32) -> Result<(), &'static str> {
+        if value >= 0 { Ok(()) } else { Err("negative") }
+    }
+
+    fn double(value: i32) -> Result<i32, String> {
+        try!(check(value));
+        let doubled = value * 2;
+        try!(check(doubled - 10));
+        Ok(doubled)
+    }
+    assert_eq!(double(6), Ok(12));
+    assert_eq!(double(-1), Err("negative".to_owned()));
+    assert_eq!(double(2), Err("negative".to_owned()));
+
+    fn checked_to_bool(value: i32) -> bool {
+        try!(check(value).map_err(|_| ()));
+        true
+    }
+    assert!(checked_to_bool(1));
+    assert!(!checked_to_bool(-1));
+}
+
+#[test]
+fn all() {
+    fn parse(a: &str, b: &str, c: &str) -> Result<(u8, u16, u32), Vec<std::num::ParseIntError>> {
+        Ok(triable_all!((a.parse::<u8>(), b.parse::<u16>(), c.parse::<u32>())))
+    }
+    assert_eq!(parse("1", "2", "3"), Ok((1, 2, 3)));
+    assert_eq!(parse("1", "x", "3"), Err(vec!["x".parse::<u16>().unwrap_err()]));
+
+    let errors = parse("300", "2", "").unwrap_err();
+    assert_eq!(errors, vec!["300".parse::<u8>().unwrap_err(), "".parse::<u32>().unwrap_err()]);
+    assert_eq!(errors[0].to_string(), "number too large to fit in target type");
+    assert_eq!(errors[1].to_string(), "cannot parse integer from empty string");
+
+    fn single() -> Result<(i32,), Vec<()>> {
+        Ok(triable_all!((Ok(4),)))
+    }
+    assert_eq!(single(), Ok((4,)));
+}
+
+#[test]
+fn or_default() {
+    #[derive(Debug, Default, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    fn from_result(x: &str, y: &str) -> Point {
+        Point { x: triable_or_default!(x.parse()), y: triable_or_default!(y.parse()) }
+    }
+    assert_eq!(from_result("1", "2"), Point { x: 1, y: 2 });
+    assert_eq!(from_result("1", "z"), Point::default());
+
+    fn from_option(x: Option<i32>) -> Point {
+        Point { x: triable_or_default!(x), y: 1 }
+    }
+    assert_eq!(from_option(Some(3)), Point { x: 3, y: 1 });
+    assert_eq!(from_option(None), Point::default());
+
+    fn from_bool(valid: bool) -> Point {
+        triable_or_default!(valid);
+        Point { x: 1, y: 1 }
+    }
+    assert_eq!(from_bool(true), Point { x: 1, y: 1 });
+    assert_eq!(from_bool(false), Point::default());
+}
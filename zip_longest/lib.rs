@@ -1,4 +1,12 @@
+#[cfg(feature = "rayon")] extern crate rayon;
+
 use std::cmp;
+use std::fmt;
+use std::vec;
+use std::iter::{self, FromIterator, FusedIterator, Peekable, Repeat, Scan, Take};
+
+#[cfg(feature = "rayon")] mod par;
+#[cfg(feature = "rayon")] pub use par::{ParZipLongestExt, ParZipLongest};
 
 pub trait ZipLongestIteratorExt: Iterator + Sized {
     /// Creates an iterator which iterates over both this and the specified
@@ -21,11 +29,50 @@ pub trait ZipLongestIteratorExt: Iterator + Sized {
     fn zip_longest<U: Iterator>(self, other: U) -> ZipLongest<Self, U> {
         ZipLongest{a: self, b: other}
     }
+
+    /// Like `zip_longest`, but double-ended even if the iterators are not `ExactSizeIterator`.
+    ///
+    /// To know how the ends of the two iterators line up,
+    /// the first call to `next_back` pulls both iterators forward together
+    /// until one of them ends, and buffers the pairs pulled so far into a vector.
+    /// This takes memory proportional to the shorter of the remaining lengths.
+    /// The rest of the longer iterator is then consumed from its back with its own `next_back`.
+    /// Iterating only forward does not buffer anything.
+    #[inline]
+    fn zip_longest_buffered<U: Iterator>(self, other: U) -> ZipLongestBuffered<Self, U> {
+        ZipLongestBuffered{inner: self.zip_longest(other), buffered: None}
+    }
+
+    /// Like `zip_longest`, but borrows both iterators
+    /// so that they can still be used after the returned adaptor is dropped.
+    #[inline]
+    fn zip_longest_by_ref<'a, U: Iterator>(&'a mut self, other: &'a mut U)
+                                           -> ZipLongest<&'a mut Self, &'a mut U> {
+        ZipLongest{a: self, b: other}
+    }
+
+    /// Creates an iterator which iterates over both this and the specified
+    /// iterators simultaneously, yielding pairs of elements.
+    /// Once one of the iterators is exhausted, its fill value is used in its place
+    /// until the other is also exhausted.
+    #[inline]
+    fn zip_pad<U: Iterator>(self, other: U, a_fill: Self::Item, b_fill: U::Item) -> ZipPad<Self, U>
+    where Self::Item: Clone, U::Item: Clone {
+        ZipPad{inner: self.zip_longest(other), a_fill, b_fill}
+    }
+
+    /// Creates an iterator which iterates over this and two other iterators
+    /// simultaneously, yielding triples of optional elements
+    /// until all three iterators are exhausted.
+    #[inline]
+    fn zip_longest3<U: Iterator, V: Iterator>(self, b: U, c: V) -> ZipLongest3<Self, U, V> {
+        ZipLongest3{a: self, b, c}
+    }
 }
 
 
 /// An iterator which iterates two other iterators simultaneously
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
 pub struct ZipLongest<T, U> {
     a: T,
@@ -82,6 +129,197 @@ where T: DoubleEndedIterator + ExactSizeIterator, U: DoubleEndedIterator + Exact
 
 impl<T: ExactSizeIterator, U: ExactSizeIterator> ExactSizeIterator for ZipLongest<T, U> {}
 
+impl<T: FusedIterator, U: FusedIterator> FusedIterator for ZipLongest<T, U> {}
+
+impl<T: Iterator, U: Iterator> ZipLongest<T, U> {
+    /// Like `Iterator::scan`: thread a mutable state through the aligned items,
+    /// yielding the outputs of `f` until it returns `None`.
+    #[inline]
+    pub fn scan_longest<S, R, F>(self, init: S, f: F) -> Scan<Self, S, F>
+    where F: FnMut(&mut S, EitherOrBoth<T::Item, U::Item>) -> Option<R> {
+        self.scan(init, f)
+    }
+}
+
+impl<T: ExactSizeIterator, U: ExactSizeIterator> ZipLongest<T, U> {
+    /// Return the number of remaining items that will be `EitherOrBoth::Both`,
+    /// the shorter of the two remaining lengths.
+    #[inline]
+    pub fn overlap_len(&self) -> usize {
+        cmp::min(self.a.len(), self.b.len())
+    }
+
+    /// Return the total number of remaining items,
+    /// the longer of the two remaining lengths.
+    #[inline]
+    pub fn total_len(&self) -> usize {
+        cmp::max(self.a.len(), self.b.len())
+    }
+}
+
+
+/// An iterator which iterates two other iterators simultaneously,
+/// buffering their overlap to support `next_back`. See `zip_longest_buffered`.
+#[derive(Clone, Debug)]
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct ZipLongestBuffered<T: Iterator, U: Iterator> {
+    inner: ZipLongest<T, U>,
+    buffered: Option<Buffered<T::Item, U::Item>>
+}
+
+/// The state of `ZipLongestBuffered` after the first call to `next_back`.
+#[derive(Clone, Debug)]
+struct Buffered<A, B> {
+    /// The remaining pairs of the overlapping prefix.
+    both: vec::IntoIter<(A, B)>,
+    /// The item of the longer side that was pulled when the shorter one ended.
+    first_rest: Option<EitherOrBoth<A, B>>,
+    /// Which iterator still has items after `first_rest`.
+    longer: Longer,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Longer {
+    A,
+    B,
+    Neither,
+}
+
+impl<T: Iterator, U: Iterator> Iterator for ZipLongestBuffered<T, U> {
+    type Item = EitherOrBoth<T::Item, U::Item>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let buffered = match self.buffered {
+            Some(ref mut buffered) => buffered,
+            None => return self.inner.next(),
+        };
+        if let Some((a, b)) = buffered.both.next() {
+            return Some(EitherOrBoth::Both(a, b))
+        }
+        if let Some(item) = buffered.first_rest.take() {
+            return Some(item)
+        }
+        match buffered.longer {
+            Longer::A => self.inner.a.next().map(EitherOrBoth::Left),
+            Longer::B => self.inner.b.next().map(EitherOrBoth::Right),
+            Longer::Neither => None,
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = match self.buffered {
+            Some(ref buffered) => buffered,
+            None => return self.inner.size_hint(),
+        };
+        let (lower, upper) = match buffered.longer {
+            Longer::A => self.inner.a.size_hint(),
+            Longer::B => self.inner.b.size_hint(),
+            Longer::Neither => (0, Some(0)),
+        };
+        let extra = buffered.both.len() + buffered.first_rest.is_some() as usize;
+        (lower.saturating_add(extra), upper.and_then(|upper| upper.checked_add(extra)))
+    }
+}
+
+impl<T, U> DoubleEndedIterator for ZipLongestBuffered<T, U>
+where T: DoubleEndedIterator, U: DoubleEndedIterator {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.buffered.is_none() {
+            let mut both = Vec::new();
+            let (first_rest, longer) = loop {
+                match (self.inner.a.next(), self.inner.b.next()) {
+                    (Some(a), Some(b)) => both.push((a, b)),
+                    (Some(a), None) => break (Some(EitherOrBoth::Left(a)), Longer::A),
+                    (None, Some(b)) => break (Some(EitherOrBoth::Right(b)), Longer::B),
+                    (None, None) => break (None, Longer::Neither),
+                }
+            };
+            self.buffered = Some(Buffered {
+                both: both.into_iter(),
+                first_rest,
+                longer,
+            });
+        }
+        let buffered = self.buffered.as_mut().unwrap();
+        let rest = match buffered.longer {
+            Longer::A => self.inner.a.next_back().map(EitherOrBoth::Left),
+            Longer::B => self.inner.b.next_back().map(EitherOrBoth::Right),
+            Longer::Neither => None,
+        };
+        rest.or_else(|| buffered.first_rest.take())
+            .or_else(|| buffered.both.next_back().map(|(a, b)| EitherOrBoth::Both(a, b)))
+    }
+}
+
+
+/// An iterator which iterates three other iterators simultaneously
+#[derive(Clone, Debug)]
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct ZipLongest3<T, U, V> {
+    a: T,
+    b: U,
+    c: V
+}
+
+impl<T: Iterator, U: Iterator, V: Iterator> Iterator for ZipLongest3<T, U, V> {
+    type Item = (Option<T::Item>, Option<U::Item>, Option<V::Item>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.a.next(), self.b.next(), self.c.next()) {
+            (None, None, None) => None,
+            triple => Some(triple),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lower, a_upper) = self.a.size_hint();
+        let (b_lower, b_upper) = self.b.size_hint();
+        let (c_lower, c_upper) = self.c.size_hint();
+
+        let lower = cmp::max(a_lower, cmp::max(b_lower, c_lower));
+
+        let upper = match (a_upper, b_upper, c_upper) {
+            (Some(x), Some(y), Some(z)) => Some(cmp::max(x, cmp::max(y, z))),
+            _ => None
+        };
+
+        (lower, upper)
+    }
+}
+
+
+/// An iterator which iterates two other iterators simultaneously,
+/// padding the shorter one with a fill value. See `zip_pad`.
+#[derive(Clone, Debug)]
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct ZipPad<T: Iterator, U: Iterator> {
+    inner: ZipLongest<T, U>,
+    a_fill: T::Item,
+    b_fill: U::Item
+}
+
+impl<T: Iterator, U: Iterator> Iterator for ZipPad<T, U> where T::Item: Clone, U::Item: Clone {
+    type Item = (T::Item, U::Item);
+
+    #[inline]
+    fn next(&mut self) -> Option<(T::Item, U::Item)> {
+        self.inner.next().map(|item| match item {
+            EitherOrBoth::Both(a, b) => (a, b),
+            EitherOrBoth::Left(a) => (a, self.b_fill.clone()),
+            EitherOrBoth::Right(b) => (self.a_fill.clone(), b),
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
 
 impl<I> ZipLongestIteratorExt for I where I: Iterator {}
 
@@ -101,6 +339,329 @@ pub enum EitherOrBoth<A, B> {
     Right(B),
 }
 
+impl<A, B> EitherOrBoth<A, B> {
+    /// Return whether there is a left value.
+    #[inline]
+    pub fn has_left(&self) -> bool {
+        match *self {
+            EitherOrBoth::Both(..) | EitherOrBoth::Left(_) => true,
+            EitherOrBoth::Right(_) => false,
+        }
+    }
+
+    /// Return whether there is a right value.
+    #[inline]
+    pub fn has_right(&self) -> bool {
+        match *self {
+            EitherOrBoth::Both(..) | EitherOrBoth::Right(_) => true,
+            EitherOrBoth::Left(_) => false,
+        }
+    }
+
+    /// Return the left value, if any.
+    #[inline]
+    pub fn left(self) -> Option<A> {
+        match self {
+            EitherOrBoth::Both(a, _) | EitherOrBoth::Left(a) => Some(a),
+            EitherOrBoth::Right(_) => None,
+        }
+    }
+
+    /// Return the right value, if any.
+    #[inline]
+    pub fn right(self) -> Option<B> {
+        match self {
+            EitherOrBoth::Both(_, b) | EitherOrBoth::Right(b) => Some(b),
+            EitherOrBoth::Left(_) => None,
+        }
+    }
+
+    /// Return both values if this is `Both`.
+    #[inline]
+    pub fn both(self) -> Option<(A, B)> {
+        match self {
+            EitherOrBoth::Both(a, b) => Some((a, b)),
+            EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => None,
+        }
+    }
+
+    /// Apply `f` to the left value, if any.
+    #[inline]
+    pub fn map_left<F, C>(self, f: F) -> EitherOrBoth<C, B> where F: FnOnce(A) -> C {
+        match self {
+            EitherOrBoth::Both(a, b) => EitherOrBoth::Both(f(a), b),
+            EitherOrBoth::Left(a) => EitherOrBoth::Left(f(a)),
+            EitherOrBoth::Right(b) => EitherOrBoth::Right(b),
+        }
+    }
+
+    /// Apply `f` to the right value, if any.
+    #[inline]
+    pub fn map_right<F, D>(self, f: F) -> EitherOrBoth<A, D> where F: FnOnce(B) -> D {
+        match self {
+            EitherOrBoth::Both(a, b) => EitherOrBoth::Both(a, f(b)),
+            EitherOrBoth::Left(a) => EitherOrBoth::Left(a),
+            EitherOrBoth::Right(b) => EitherOrBoth::Right(f(b)),
+        }
+    }
+
+    /// Apply `f` to the left value and `g` to the right value, whichever are present.
+    #[inline]
+    pub fn map_any<F, G, C, D>(self, f: F, g: G) -> EitherOrBoth<C, D>
+    where F: FnOnce(A) -> C, G: FnOnce(B) -> D {
+        match self {
+            EitherOrBoth::Both(a, b) => EitherOrBoth::Both(f(a), g(b)),
+            EitherOrBoth::Left(a) => EitherOrBoth::Left(f(a)),
+            EitherOrBoth::Right(b) => EitherOrBoth::Right(g(b)),
+        }
+    }
+
+    /// Return the values of each side, if present.
+    #[inline]
+    pub fn left_and_right(self) -> (Option<A>, Option<B>) {
+        match self {
+            EitherOrBoth::Both(a, b) => (Some(a), Some(b)),
+            EitherOrBoth::Left(a) => (Some(a), None),
+            EitherOrBoth::Right(b) => (None, Some(b)),
+        }
+    }
+
+    /// Return the values of each side, if present.
+    /// This is the same as `left_and_right`.
+    #[inline]
+    pub fn into_options(self) -> (Option<A>, Option<B>) {
+        self.left_and_right()
+    }
+
+    /// Convert from `&EitherOrBoth<A, B>` to `EitherOrBoth<&A, &B>`.
+    #[inline]
+    pub fn as_ref(&self) -> EitherOrBoth<&A, &B> {
+        match *self {
+            EitherOrBoth::Both(ref a, ref b) => EitherOrBoth::Both(a, b),
+            EitherOrBoth::Left(ref a) => EitherOrBoth::Left(a),
+            EitherOrBoth::Right(ref b) => EitherOrBoth::Right(b),
+        }
+    }
+
+    /// Return both values, using the given values for a side that is missing.
+    #[inline]
+    pub fn or(self, left: A, right: B) -> (A, B) {
+        match self {
+            EitherOrBoth::Both(a, b) => (a, b),
+            EitherOrBoth::Left(a) => (a, right),
+            EitherOrBoth::Right(b) => (left, b),
+        }
+    }
+
+    /// Return both values, using `Default::default()` for a side that is missing.
+    #[inline]
+    pub fn or_default(self) -> (A, B) where A: Default, B: Default {
+        match self {
+            EitherOrBoth::Both(a, b) => (a, b),
+            EitherOrBoth::Left(a) => (a, B::default()),
+            EitherOrBoth::Right(b) => (A::default(), b),
+        }
+    }
+
+    /// Return the number of values contained: 2 for `Both`, 1 for `Left` or `Right`.
+    #[inline]
+    pub fn count_sides(&self) -> usize {
+        match *self {
+            EitherOrBoth::Both(..) => 2,
+            EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => 1,
+        }
+    }
+
+    /// Return references to the values present on each side.
+    #[inline]
+    pub fn as_options(&self) -> (Option<&A>, Option<&B>) {
+        match *self {
+            EitherOrBoth::Both(ref a, ref b) => (Some(a), Some(b)),
+            EitherOrBoth::Left(ref a) => (Some(a), None),
+            EitherOrBoth::Right(ref b) => (None, Some(b)),
+        }
+    }
+
+    /// Return references to both values,
+    /// using the given defaults for a side that is missing.
+    #[inline]
+    pub fn as_tuple_or<'a>(&'a self, left: &'a A, right: &'a B) -> (&'a A, &'a B) {
+        match *self {
+            EitherOrBoth::Both(ref a, ref b) => (a, b),
+            EitherOrBoth::Left(ref a) => (a, right),
+            EitherOrBoth::Right(ref b) => (left, b),
+        }
+    }
+}
+
+impl<A> EitherOrBoth<A, A> {
+    /// Return the single value of `Left` or `Right`,
+    /// or combine the two values of `Both` with `f`.
+    #[inline]
+    pub fn merge<F: FnOnce(A, A) -> A>(self, f: F) -> A {
+        match self {
+            EitherOrBoth::Both(a, b) => f(a, b),
+            EitherOrBoth::Left(a) => a,
+            EitherOrBoth::Right(b) => b,
+        }
+    }
+}
+
+/// Split into two collections, one for the values present on each side.
+/// This reverses `zip_longest`.
+impl<A, B, CA, CB> FromIterator<EitherOrBoth<A, B>> for (CA, CB)
+where CA: Default + Extend<A>, CB: Default + Extend<B> {
+    fn from_iter<I: IntoIterator<Item = EitherOrBoth<A, B>>>(iter: I) -> (CA, CB) {
+        let mut left = CA::default();
+        let mut right = CB::default();
+        for item in iter {
+            match item {
+                EitherOrBoth::Both(a, b) => {
+                    left.extend(Some(a));
+                    right.extend(Some(b));
+                }
+                EitherOrBoth::Left(a) => left.extend(Some(a)),
+                EitherOrBoth::Right(b) => right.extend(Some(b)),
+            }
+        }
+        (left, right)
+    }
+}
+
+impl<A, B> From<EitherOrBoth<A, B>> for (Option<A>, Option<B>) {
+    #[inline]
+    fn from(item: EitherOrBoth<A, B>) -> (Option<A>, Option<B>) {
+        item.into_options()
+    }
+}
+
+/// Return a vector of the one or two values.
+impl<A> From<EitherOrBoth<A, A>> for Vec<A> {
+    fn from(item: EitherOrBoth<A, A>) -> Vec<A> {
+        match item {
+            EitherOrBoth::Both(a, b) => vec![a, b],
+            EitherOrBoth::Left(a) => vec![a],
+            EitherOrBoth::Right(b) => vec![b],
+        }
+    }
+}
+
+/// Only `Both` can be equal to a pair, when both components are equal.
+impl<A: PartialEq, B: PartialEq> PartialEq<(A, B)> for EitherOrBoth<A, B> {
+    #[inline]
+    fn eq(&self, other: &(A, B)) -> bool {
+        match *self {
+            EitherOrBoth::Both(ref a, ref b) => *a == other.0 && *b == other.1,
+            EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => false,
+        }
+    }
+}
+
+
+/// Return the index of the first position where the two sequences differ,
+/// either by value or because one of them is exhausted before the other.
+/// Return `None` if the sequences are identical.
+pub fn first_divergence<A, I, J>(a: I, b: J) -> Option<usize>
+where A: PartialEq, I: IntoIterator<Item = A>, J: IntoIterator<Item = A> {
+    a.into_iter().zip_longest(b.into_iter()).position(|item| match item {
+        EitherOrBoth::Both(a, b) => a != b,
+        EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => true,
+    })
+}
+
+
+/// Pad an iterator with clones of `pad` so that it yields at least `len` elements.
+/// An iterator that is already longer is left as-is.
+// `iter::repeat_n` would require Rust 1.82.
+#[allow(clippy::manual_repeat_n)]
+pub fn pad_to<I: IntoIterator>(iter: I, len: usize, pad: I::Item) -> PadTo<I::IntoIter>
+where I::Item: Clone {
+    PadTo{inner: iter.into_iter().zip_longest(iter::repeat(pad).take(len))}
+}
+
+
+/// An iterator that pads another iterator to a minimum length. See `pad_to`.
+#[derive(Clone, Debug)]
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct PadTo<I: Iterator> {
+    inner: ZipLongest<I, Take<Repeat<I::Item>>>
+}
+
+impl<I: Iterator> Iterator for PadTo<I> where I::Item: Clone {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        self.inner.next().map(|item| match item {
+            EitherOrBoth::Both(value, _) | EitherOrBoth::Left(value) => value,
+            EitherOrBoth::Right(pad) => pad,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+
+/// Drop repeated equal pairs, for example to compress the output of diffing
+/// two similar sequences.
+///
+/// An item `Both(a, b)` where `a == b` is dropped if it is equal
+/// to the item emitted just before it.
+/// All other items (`Left`, `Right`, and `Both` with different values) are emitted.
+pub fn dedup_both<T, I>(iter: I) -> DedupBoth<I::IntoIter>
+where T: PartialEq, I: IntoIterator<Item = EitherOrBoth<T, T>> {
+    DedupBoth{iter: iter.into_iter().peekable()}
+}
+
+
+/// An iterator that drops repeated equal pairs. See `dedup_both`.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct DedupBoth<I: Iterator> {
+    iter: Peekable<I>
+}
+
+impl<I: Iterator + Clone> Clone for DedupBoth<I> where I::Item: Clone {
+    fn clone(&self) -> Self {
+        DedupBoth{iter: self.iter.clone()}
+    }
+}
+
+impl<I: Iterator + fmt::Debug> fmt::Debug for DedupBoth<I> where I::Item: fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DedupBoth").field("iter", &self.iter).finish()
+    }
+}
+
+impl<T, I> Iterator for DedupBoth<I>
+where T: PartialEq, I: Iterator<Item = EitherOrBoth<T, T>> {
+    type Item = EitherOrBoth<T, T>;
+
+    fn next(&mut self) -> Option<EitherOrBoth<T, T>> {
+        let item = self.iter.next()?;
+        loop {
+            let is_duplicate = match self.iter.peek() {
+                Some(next) => match *next {
+                    EitherOrBoth::Both(ref a, ref b) => a == b && *next == item,
+                    EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => false,
+                },
+                None => false,
+            };
+            if !is_duplicate {
+                return Some(item)
+            }
+            self.iter.next();
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (cmp::min(lower, 1), upper)
+    }
+}
 
 #[test]
 fn test_iterator_size_hint() {
@@ -114,6 +675,22 @@ fn test_iterator_size_hint() {
     assert_eq!(vi.zip_longest(v2.iter()).size_hint(), (10, Some(10)));
 }
 
+#[test]
+fn test_exact_size_hint() {
+    let xs = [1, 2, 3, 4, 5];
+    for a_len in 0..xs.len() {
+        for b_len in 0..xs.len() {
+            let it = xs[..a_len].iter().zip_longest(xs[..b_len].iter());
+            let len = it.clone().count();
+            assert_eq!(it.size_hint(), (len, Some(len)));
+            assert_eq!(it.len(), len);
+        }
+    }
+
+    let it = xs.iter().zip_longest(xs.iter().filter(|&&x| x > 2));
+    assert_eq!(it.size_hint(), (5, Some(5)));
+}
+
 #[test]
 fn test_double_ended() {
     let xs = [1i32, 2, 3, 4, 5, 6];
@@ -129,3 +706,357 @@ fn test_double_ended() {
     assert_eq!(it.next(), Some(EitherOrBoth::Both(3, 3)));
     assert_eq!(it.next(), None);
 }
+
+#[test]
+fn test_every_position() {
+    let xs = [1i32, 2, 3, 4, 5];
+    let ys = [7i32, 8, 9];
+    let expected: Vec<_> = (0..5).map(|i| match (xs.get(i), ys.get(i)) {
+        (Some(x), Some(y)) => EitherOrBoth::Both(x, y),
+        (Some(x), None) => EitherOrBoth::Left(x),
+        (None, Some(y)) => EitherOrBoth::Right(y),
+        (None, None) => unreachable!(),
+    }).collect();
+
+    let it = xs.iter().zip_longest(ys.iter());
+    assert_eq!(it.len(), 5);
+    assert_eq!(it.clone().collect::<Vec<_>>(), expected);
+    assert_eq!(it.rev().collect::<Vec<_>>(), expected.iter().cloned().rev().collect::<Vec<_>>());
+
+    let swapped: Vec<_> = ys.iter().zip_longest(xs.iter()).collect();
+    let expected_swapped: Vec<_> = expected.into_iter().map(|item| match item {
+        EitherOrBoth::Both(x, y) => EitherOrBoth::Both(y, x),
+        EitherOrBoth::Left(x) => EitherOrBoth::Right(x),
+        EitherOrBoth::Right(y) => EitherOrBoth::Left(y),
+    }).collect();
+    assert_eq!(swapped, expected_swapped);
+}
+
+#[test]
+fn test_eq_tuple() {
+    assert!(EitherOrBoth::Both(1, 2) == (1, 2));
+    assert!(EitherOrBoth::Both(1, 2) != (1, 3));
+    assert!(EitherOrBoth::Left::<i32, i32>(1) != (1, 0));
+    assert!(EitherOrBoth::Right::<i32, i32>(0) != (1, 0));
+}
+
+#[test]
+fn test_first_divergence() {
+    assert_eq!(first_divergence("abc".chars(), "abc".chars()), None);
+    assert_eq!(first_divergence("".chars(), "".chars()), None);
+    assert_eq!(first_divergence("abc".chars(), "abd".chars()), Some(2));
+    assert_eq!(first_divergence("abc".chars(), "ab".chars()), Some(2));
+    assert_eq!(first_divergence("a".chars(), "abc".chars()), Some(1));
+}
+
+#[test]
+fn test_count_sides() {
+    assert_eq!(EitherOrBoth::Both::<i32, i32>(1, 2).count_sides(), 2);
+    assert_eq!(EitherOrBoth::Left::<i32, i32>(1).count_sides(), 1);
+    assert_eq!(EitherOrBoth::Right::<i32, i32>(2).count_sides(), 1);
+
+    let total: usize = [1, 2, 3].iter().zip_longest([4, 5].iter()).map(|e| e.count_sides()).sum();
+    assert_eq!(total, 5);
+}
+
+#[test]
+fn test_merge() {
+    use std::ops::Add;
+
+    assert_eq!(EitherOrBoth::Both(1, 2).merge(Add::add), 3);
+    assert_eq!(EitherOrBoth::Left(1).merge(Add::add), 1);
+    assert_eq!(EitherOrBoth::Right(2).merge(Add::add), 2);
+
+    assert_eq!(EitherOrBoth::Both(1, 2).merge(cmp::max), 2);
+    assert_eq!(EitherOrBoth::Both(5, 2).merge(cmp::max), 5);
+    assert_eq!(EitherOrBoth::Left(1).merge(cmp::max), 1);
+    assert_eq!(EitherOrBoth::Right(2).merge(cmp::max), 2);
+}
+
+#[test]
+fn test_overlap_and_total_len() {
+    let xs = [1, 2, 3, 4, 5];
+    let ys = [1, 2];
+    let mut it = xs.iter().zip_longest(ys.iter());
+    assert_eq!((it.overlap_len(), it.total_len()), (2, 5));
+    it.next();
+    assert_eq!((it.overlap_len(), it.total_len()), (1, 4));
+    it.next();
+    it.next();
+    assert_eq!((it.overlap_len(), it.total_len()), (0, 2));
+
+    let it = ys.iter().zip_longest(xs.iter());
+    assert_eq!((it.overlap_len(), it.total_len()), (2, 5));
+}
+
+#[test]
+fn test_as_tuple_or() {
+    let (x, y) = (String::from("x"), String::from("y"));
+    let both = EitherOrBoth::Both(String::from("a"), String::from("b"));
+    let left = EitherOrBoth::Left(String::from("a"));
+    let right = EitherOrBoth::Right(String::from("b"));
+    assert_eq!(both.as_tuple_or(&x, &y), (&String::from("a"), &String::from("b")));
+    assert_eq!(left.as_tuple_or(&x, &y), (&String::from("a"), &y));
+    assert_eq!(right.as_tuple_or(&x, &y), (&x, &String::from("b")));
+}
+
+#[test]
+fn test_collect_pair() {
+    let xs = vec![1, 2, 3, 4];
+    let ys = vec!["a", "b"];
+    let (a, b): (Vec<i32>, Vec<&str>) = xs.clone().into_iter().zip_longest(ys.clone().into_iter()).collect();
+    assert_eq!((a, b), (xs.clone(), ys.clone()));
+    let (b, a): (Vec<&str>, Vec<i32>) = ys.clone().into_iter().zip_longest(xs.clone().into_iter()).collect();
+    assert_eq!((a, b), (xs, ys));
+}
+
+#[test]
+fn test_scan_longest() {
+    let xs = [1, 2, 3, 4];
+    let ys = [10, 20];
+    let running: Vec<i32> = xs.iter().zip_longest(ys.iter()).scan_longest(0, |sum, item| {
+        *sum += match item {
+            EitherOrBoth::Both(x, y) => x + y,
+            EitherOrBoth::Left(x) => *x,
+            EitherOrBoth::Right(y) => *y,
+        };
+        Some(*sum)
+    }).collect();
+    assert_eq!(running, [11, 33, 36, 40]);
+}
+
+#[test]
+fn test_as_options() {
+    let both: EitherOrBoth<i32, char> = EitherOrBoth::Both(1, 'b');
+    let left: EitherOrBoth<i32, char> = EitherOrBoth::Left(1);
+    let right: EitherOrBoth<i32, char> = EitherOrBoth::Right('b');
+    assert_eq!(both.as_options(), (Some(&1), Some(&'b')));
+    assert_eq!(left.as_options(), (Some(&1), None));
+    assert_eq!(right.as_options(), (None, Some(&'b')));
+}
+
+#[test]
+fn test_pad_to() {
+    let padded: Vec<i32> = pad_to(vec![1, 2], 4, 0).collect();
+    assert_eq!(padded, [1, 2, 0, 0]);
+    assert_eq!(pad_to(vec![1, 2], 4, 0).size_hint(), (4, Some(4)));
+
+    let unchanged: Vec<i32> = pad_to(vec![1, 2, 3], 2, 0).collect();
+    assert_eq!(unchanged, [1, 2, 3]);
+}
+
+#[test]
+fn test_into_vec() {
+    assert_eq!(Vec::from(EitherOrBoth::Both(1, 2)), [1, 2]);
+    assert_eq!(Vec::from(EitherOrBoth::Left(1)), [1]);
+    assert_eq!(Vec::from(EitherOrBoth::Right(2)), [2]);
+
+    let flat: Vec<i32> = [1, 2, 3].iter().zip_longest([4].iter()).flat_map(Vec::from).cloned().collect();
+    assert_eq!(flat, [1, 4, 2, 3]);
+}
+
+#[test]
+fn test_dedup_both() {
+    use EitherOrBoth::{Both, Left, Right};
+
+    let items = vec![Both(1, 1), Both(1, 1), Both(1, 1), Left(2), Left(2), Both(1, 1),
+                     Both(1, 2), Both(1, 2), Right(3), Both(4, 4), Both(4, 4)];
+    let deduped: Vec<_> = dedup_both(items).collect();
+    assert_eq!(deduped, [Both(1, 1), Left(2), Left(2), Both(1, 1),
+                         Both(1, 2), Both(1, 2), Right(3), Both(4, 4)]);
+}
+
+#[test]
+fn test_accessors() {
+    use EitherOrBoth::{Both, Left, Right};
+
+    let both: EitherOrBoth<i32, char> = Both(1, 'b');
+    let left: EitherOrBoth<i32, char> = Left(1);
+    let right: EitherOrBoth<i32, char> = Right('b');
+
+    assert_eq!((both.has_left(), left.has_left(), right.has_left()), (true, true, false));
+    assert_eq!((both.has_right(), left.has_right(), right.has_right()), (true, false, true));
+
+    assert_eq!((both.clone().left(), left.clone().left(), right.clone().left()), (Some(1), Some(1), None));
+    assert_eq!((both.clone().right(), left.clone().right(), right.clone().right()),
+               (Some('b'), None, Some('b')));
+    assert_eq!((both.clone().both(), left.clone().both(), right.clone().both()), (Some((1, 'b')), None, None));
+
+    let double = |x: i32| x * 2;
+    let upper = |c: char| c.to_ascii_uppercase();
+    assert_eq!(both.clone().map_left(double), Both(2, 'b'));
+    assert_eq!(left.clone().map_left(double), Left(2));
+    assert_eq!(right.clone().map_left(double), Right('b'));
+    assert_eq!(both.clone().map_right(upper), Both(1, 'B'));
+    assert_eq!(left.clone().map_right(upper), Left(1));
+    assert_eq!(right.clone().map_right(upper), Right('B'));
+    assert_eq!(both.map_any(double, upper), Both(2, 'B'));
+    assert_eq!(left.map_any(double, upper), Left(2));
+    assert_eq!(right.map_any(double, upper), Right('B'));
+}
+
+#[test]
+fn test_fill_missing() {
+    use EitherOrBoth::{Both, Left, Right};
+
+    assert_eq!(Both::<i32, String>(1, "b".to_owned()).or_default(), (1, "b".to_owned()));
+    assert_eq!(Left::<i32, String>(1).or_default(), (1, String::new()));
+    assert_eq!(Right::<i32, String>("b".to_owned()).or_default(), (0, "b".to_owned()));
+
+    assert_eq!(Both(1, 'b').or(0, 'z'), (1, 'b'));
+    assert_eq!(Left(1).or(0, 'z'), (1, 'z'));
+    assert_eq!(Right('b').or(0, 'z'), (0, 'b'));
+
+    assert_eq!(Both(1, 'b').left_and_right(), (Some(1), Some('b')));
+    assert_eq!(Left::<i32, char>(1).left_and_right(), (Some(1), None));
+    assert_eq!(Right::<i32, char>('b').left_and_right(), (None, Some('b')));
+}
+
+#[test]
+fn test_zip_pad() {
+    let padded: Vec<(i32, char)> = [1, 2, 3].iter().cloned().zip_pad("ab".chars(), 0, '-').collect();
+    assert_eq!(padded, [(1, 'a'), (2, 'b'), (3, '-')]);
+
+    let padded: Vec<(i32, char)> = [1].iter().cloned().zip_pad("abc".chars(), 0, '-').collect();
+    assert_eq!(padded, [(1, 'a'), (0, 'b'), (0, 'c')]);
+
+    let it = [1, 2, 3].iter().zip_pad([4].iter(), &0, &0);
+    assert_eq!(it.size_hint(), (3, Some(3)));
+}
+
+#[test]
+fn test_fused() {
+    fn assert_fused<I: FusedIterator>(_: &I) {}
+
+    let mut it = [1, 2].iter().zip_longest([3].iter());
+    assert_fused(&it);
+    assert_eq!(it.next(), Some(EitherOrBoth::Both(&1, &3)));
+    assert_eq!(it.next(), Some(EitherOrBoth::Left(&2)));
+    for _ in 0..5 {
+        assert_eq!(it.next(), None);
+    }
+}
+
+#[test]
+fn test_debug() {
+    let it = [1, 2].iter().zip_longest(Some('x').into_iter());
+    let debug = format!("{:?}", it);
+    assert!(debug.starts_with("ZipLongest { a: Iter([1, 2]), b: "), "{}", debug);
+    assert!(debug.contains("'x'"), "{}", debug);
+
+    let padded = format!("{:?}", [1, 2].iter().zip_pad(None::<char>.into_iter(), &0, '-'));
+    assert!(padded.starts_with("ZipPad { inner: ZipLongest { "), "{}", padded);
+    assert!(padded.ends_with("a_fill: 0, b_fill: '-' }"), "{}", padded);
+    assert!(format!("{:?}", pad_to(vec![1], 2, 0)).starts_with("PadTo { inner: "));
+    let deduped = dedup_both(vec![EitherOrBoth::Left(1)]);
+    assert!(format!("{:?}", deduped).starts_with("DedupBoth { iter: Peekable { "));
+}
+
+#[test]
+fn test_zip_longest3() {
+    let long = [1, 2, 3];
+    let short = [4];
+    let empty: [i32; 0] = [];
+
+    let mut it = long.iter().zip_longest3(short.iter(), empty.iter());
+    assert_eq!(it.size_hint(), (3, Some(3)));
+    assert_eq!(it.next(), Some((Some(&1), Some(&4), None)));
+    assert_eq!(it.next(), Some((Some(&2), None, None)));
+    assert_eq!(it.next(), Some((Some(&3), None, None)));
+    assert_eq!(it.next(), None);
+
+    let mut it = short.iter().zip_longest3(long.iter(), empty.iter());
+    assert_eq!(it.size_hint(), (3, Some(3)));
+    assert_eq!(it.next(), Some((Some(&4), Some(&1), None)));
+    assert_eq!(it.next(), Some((None, Some(&2), None)));
+    assert_eq!(it.next(), Some((None, Some(&3), None)));
+    assert_eq!(it.next(), None);
+
+    let mut it = empty.iter().zip_longest3(short.iter(), long.iter());
+    assert_eq!(it.size_hint(), (3, Some(3)));
+    assert_eq!(it.next(), Some((None, Some(&4), Some(&1))));
+    assert_eq!(it.next(), Some((None, None, Some(&2))));
+    assert_eq!(it.next(), Some((None, None, Some(&3))));
+    assert_eq!(it.next(), None);
+
+    let it = empty.iter().zip_longest3(short.iter(), 0..);
+    assert_eq!(it.size_hint(), (usize::MAX, None));
+}
+
+#[test]
+fn test_into_options() {
+    use EitherOrBoth::{Both, Left, Right};
+
+    assert_eq!(Both(1, 'b').into_options(), (Some(1), Some('b')));
+    assert_eq!(Left::<i32, char>(1).into_options(), (Some(1), None));
+    assert_eq!(Right::<i32, char>('b').into_options(), (None, Some('b')));
+
+    assert_eq!(<(Option<i32>, Option<char>)>::from(Both(1, 'b')), (Some(1), Some('b')));
+    assert_eq!(<(Option<i32>, Option<char>)>::from(Left(1)), (Some(1), None));
+    assert_eq!(<(Option<i32>, Option<char>)>::from(Right('b')), (None, Some('b')));
+
+    let (a, b): (Vec<Option<i32>>, Vec<Option<i32>>) =
+        [1, 2].iter().cloned().zip_longest(Some(3).into_iter()).map(EitherOrBoth::into_options).unzip();
+    assert_eq!((a, b), (vec![Some(1), Some(2)], vec![Some(3), None]));
+
+    let both = Both(String::from("a"), 'b');
+    assert_eq!(both.as_ref(), Both(&String::from("a"), &'b'));
+    assert_eq!(Left::<i32, char>(1).as_ref(), Left(&1));
+    assert_eq!(Right::<i32, char>('b').as_ref(), Right(&'b'));
+}
+
+#[test]
+fn test_zip_longest_by_ref() {
+    let mut a = vec![1, 2, 3, 4].into_iter();
+    let mut b = vec![5, 6].into_iter();
+    {
+        let mut it = a.zip_longest_by_ref(&mut b);
+        assert_eq!(it.next(), Some(EitherOrBoth::Both(1, 5)));
+        assert_eq!(it.next(), Some(EitherOrBoth::Both(2, 6)));
+        assert_eq!(it.next(), Some(EitherOrBoth::Left(3)));
+    }
+    assert_eq!(b.next(), None);
+    assert_eq!(a.collect::<Vec<_>>(), [4]);
+}
+
+#[test]
+fn test_buffered_double_ended() {
+    let a = "abcdef".chars();
+    let b = [1, 2, 3, 4, 5, 6, 7, 8].iter().filter(|&&x| x % 2 == 0);
+    let mut it = a.zip_longest_buffered(b);
+    assert_eq!(it.next(), Some(EitherOrBoth::Both('a', &2)));
+    assert_eq!(it.next_back(), Some(EitherOrBoth::Left('f')));
+    assert_eq!(it.next_back(), Some(EitherOrBoth::Left('e')));
+    assert_eq!(it.next_back(), Some(EitherOrBoth::Both('d', &8)));
+    assert_eq!(it.size_hint(), (2, Some(2)));
+    assert_eq!(it.next(), Some(EitherOrBoth::Both('b', &4)));
+    assert_eq!(it.next_back(), Some(EitherOrBoth::Both('c', &6)));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+
+    let reversed: Vec<_> = "ab".chars().zip_longest_buffered("xyz".chars()).rev().collect();
+    assert_eq!(reversed, [EitherOrBoth::Right('z'), EitherOrBoth::Both('b', 'y'), EitherOrBoth::Both('a', 'x')]);
+}
+
+#[test]
+fn test_buffered_only_the_shorter_side() {
+    let mut long = (0..1_000_000).filter(|_| true);
+    {
+        let mut it = long.by_ref().zip_longest_buffered(iter::once('x').filter(|_| true));
+        assert_eq!(it.next_back(), Some(EitherOrBoth::Left(999_999)));
+        assert_eq!(it.next_back(), Some(EitherOrBoth::Left(999_998)));
+        assert_eq!(it.next(), Some(EitherOrBoth::Both(0, 'x')));
+        assert_eq!(it.next(), Some(EitherOrBoth::Left(1)));
+        assert_eq!(it.size_hint(), (0, Some(999_996)));
+    }
+    assert_eq!(long.next(), Some(2));
+    assert_eq!(long.next_back(), Some(999_997));
+
+    let mut it = [1, 2].iter().filter(|_| true).zip_longest_buffered("abcd".chars().filter(|_| true));
+    assert_eq!(it.next_back(), Some(EitherOrBoth::Right('d')));
+    assert_eq!(it.next_back(), Some(EitherOrBoth::Right('c')));
+    assert_eq!(it.next_back(), Some(EitherOrBoth::Both(&2, 'b')));
+    assert_eq!(it.next(), Some(EitherOrBoth::Both(&1, 'a')));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+}
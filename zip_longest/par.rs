@@ -0,0 +1,187 @@
+//! Parallel `zip_longest` for rayon’s indexed parallel iterators,
+//! enabled with the `rayon` Cargo feature.
+
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
+use std::cmp;
+use {EitherOrBoth, ZipLongest};
+
+pub trait ParZipLongestExt: IndexedParallelIterator {
+    /// Creates a parallel iterator which iterates over both this and the specified
+    /// parallel iterators simultaneously, yielding pairs of two optional elements.
+    /// Its length is the larger of the two lengths.
+    #[inline]
+    fn zip_longest<U>(self, other: U) -> ParZipLongest<Self, U::Iter>
+    where U: IntoParallelIterator, U::Iter: IndexedParallelIterator {
+        ParZipLongest{a: self, b: other.into_par_iter()}
+    }
+}
+
+impl<I> ParZipLongestExt for I where I: IndexedParallelIterator {}
+
+
+/// A parallel iterator which iterates two other parallel iterators simultaneously
+#[derive(Clone, Debug)]
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct ParZipLongest<A, B> {
+    a: A,
+    b: B
+}
+
+impl<A, B> ParallelIterator for ParZipLongest<A, B>
+where A: IndexedParallelIterator, B: IndexedParallelIterator {
+    type Item = EitherOrBoth<A::Item, B::Item>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where C: UnindexedConsumer<Self::Item> {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<A, B> IndexedParallelIterator for ParZipLongest<A, B>
+where A: IndexedParallelIterator, B: IndexedParallelIterator {
+    fn drive<C>(self, consumer: C) -> C::Result
+    where C: Consumer<Self::Item> {
+        bridge(self, consumer)
+    }
+
+    fn len(&self) -> usize {
+        cmp::max(self.a.len(), self.b.len())
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where CB: ProducerCallback<Self::Item> {
+        let a_len = self.a.len();
+        let b_len = self.b.len();
+        return self.a.with_producer(CallbackA {
+            callback,
+            b: self.b,
+            a_len,
+            b_len,
+        });
+
+        struct CallbackA<CB, B> {
+            callback: CB,
+            b: B,
+            a_len: usize,
+            b_len: usize,
+        }
+
+        impl<CB, ItemA, B> ProducerCallback<ItemA> for CallbackA<CB, B>
+        where B: IndexedParallelIterator, CB: ProducerCallback<EitherOrBoth<ItemA, B::Item>> {
+            type Output = CB::Output;
+
+            fn callback<P>(self, a_producer: P) -> Self::Output
+            where P: Producer<Item = ItemA> {
+                self.b.with_producer(CallbackB {
+                    callback: self.callback,
+                    a_producer,
+                    a_len: self.a_len,
+                    b_len: self.b_len,
+                })
+            }
+        }
+
+        struct CallbackB<CB, A> {
+            callback: CB,
+            a_producer: A,
+            a_len: usize,
+            b_len: usize,
+        }
+
+        impl<CB, A, ItemB> ProducerCallback<ItemB> for CallbackB<CB, A>
+        where A: Producer, CB: ProducerCallback<EitherOrBoth<A::Item, ItemB>> {
+            type Output = CB::Output;
+
+            fn callback<P>(self, b_producer: P) -> Self::Output
+            where P: Producer<Item = ItemB> {
+                self.callback.callback(ZipLongestProducer {
+                    a: self.a_producer,
+                    b: b_producer,
+                    a_len: self.a_len,
+                    b_len: self.b_len,
+                })
+            }
+        }
+    }
+}
+
+
+/// Splits both sides at the same index,
+/// clamped to the length of each side so that the shorter one runs out first.
+struct ZipLongestProducer<A, B> {
+    a: A,
+    b: B,
+    a_len: usize,
+    b_len: usize,
+}
+
+impl<A: Producer, B: Producer> Producer for ZipLongestProducer<A, B> {
+    type Item = EitherOrBoth<A::Item, B::Item>;
+    type IntoIter = ZipLongest<A::IntoIter, B::IntoIter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ZipLongest{a: self.a.into_iter(), b: self.b.into_iter()}
+    }
+
+    fn min_len(&self) -> usize {
+        cmp::max(self.a.min_len(), self.b.min_len())
+    }
+
+    fn max_len(&self) -> usize {
+        cmp::min(self.a.max_len(), self.b.max_len())
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let a_index = cmp::min(index, self.a_len);
+        let b_index = cmp::min(index, self.b_len);
+        let (a_left, a_right) = self.a.split_at(a_index);
+        let (b_left, b_right) = self.b.split_at(b_index);
+        let left = ZipLongestProducer {
+            a: a_left,
+            b: b_left,
+            a_len: a_index,
+            b_len: b_index,
+        };
+        let right = ZipLongestProducer {
+            a: a_right,
+            b: b_right,
+            a_len: self.a_len - a_index,
+            b_len: self.b_len - b_index,
+        };
+        (left, right)
+    }
+}
+
+
+#[test]
+fn test_par_zip_longest() {
+    use rayon::prelude::*;
+    use ZipLongestIteratorExt;
+
+    fn sum(item: EitherOrBoth<&u64, &u64>) -> u64 {
+        match item {
+            EitherOrBoth::Both(a, b) => a + b,
+            EitherOrBoth::Left(a) => *a,
+            EitherOrBoth::Right(b) => *b,
+        }
+    }
+
+    let a: Vec<u64> = (0..1000).collect();
+    let b: Vec<u64> = (0..1500).map(|x| x * 3).collect();
+    let sequential: Vec<u64> = a.iter().zip_longest(b.iter()).map(sum).collect();
+
+    let parallel: Vec<u64> = a.par_iter().zip_longest(&b).map(sum).collect();
+    assert_eq!(a.par_iter().zip_longest(&b).len(), 1500);
+    assert_eq!(parallel, sequential);
+
+    let parallel: Vec<u64> = b.par_iter().zip_longest(&a).map(sum).collect();
+    assert_eq!(parallel, sequential);
+
+    let total: u64 = a.par_iter().zip_longest(&b).map(sum).sum();
+    assert_eq!(total, sequential.iter().sum());
+}